@@ -1,6 +1,9 @@
 use async_std::task;
+use futures::channel::{mpsc, oneshot};
 use futures::join;
-use futures::stream::{FuturesUnordered, StreamExt};
+use futures::future::{self, abortable, Either};
+use futures::sink::SinkExt;
+use futures::stream::{self, FuturesOrdered, FuturesUnordered, Stream, StreamExt};
 use std::time::{Duration, Instant};
 use std::thread;
 use rand::distributions::{Distribution, Uniform};
@@ -12,6 +15,11 @@ fn main() {
     //demo_waiting_for_multiple_random_sleeps();
     //demo_waiting_for_multiple_random_sleeps_with_return_values();
     //demo_waiting_for_multiple_random_sleeps_with_errors();
+    //demo_downloading_urls();
+    //demo_downloading_urls_bounded(4);
+    //demo_downloading_urls_in_order();
+    //demo_downloading_urls_with_timeout_and_cancellation();
+    //demo_channel_coordination();
     demo_downloading_urls();
 
     println!("Program finished in {} ms", start_time.elapsed().as_millis());
@@ -156,6 +164,221 @@ async fn download_url(url: &str) -> Result<String, surf::Exception> {
     Ok(body)
 }
 
+/// Stands in for genuinely CPU-heavy post-processing of a downloaded
+/// body (e.g. scraping a price out of the HTML, or hashing the page to
+/// detect changes between runs). The repeated passes over `body` are
+/// what make this expensive enough to matter: run it synchronously on
+/// the async executor's thread and it blocks every other task
+/// scheduled there for as long as it takes to finish. `demo_downloading_urls`
+/// instead runs it via `task::spawn_blocking`, on async-std's dedicated
+/// blocking thread pool, so the reactor stays responsive.
+fn compute_content_hash(body: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+
+    for _pass in 0..200 {
+        for byte in body.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    hash
+}
+
+/// The error returned by `download_url_with_timeout`: either the
+/// underlying request failed, or the deadline elapsed first.
+#[derive(Debug)]
+enum DownloadError {
+    Request(surf::Exception),
+    TimedOut,
+}
+
+/// Races `download_url` against `task::sleep(timeout)` using
+/// `future::select`. Whichever future resolves first "wins"; if the
+/// sleep wins, the download is dropped (and so cancelled) and we
+/// report `DownloadError::TimedOut` instead of waiting for it any
+/// longer.
+async fn download_url_with_timeout(url: &str, timeout: Duration) -> Result<String, DownloadError> {
+    let download = Box::pin(download_url(url));
+    let timeout = Box::pin(task::sleep(timeout));
+
+    match future::select(download, timeout).await {
+        Either::Left((result, _)) => result.map_err(DownloadError::Request),
+        Either::Right((_, _)) => Err(DownloadError::TimedOut),
+    }
+}
+
+/// Wraps `download_url` in a retry loop with exponential backoff: on
+/// failure it sleeps for a delay that doubles each attempt (starting at
+/// `100ms`), with a small random jitter mixed in so that many failing
+/// downloads don't all retry in lockstep, and gives up after
+/// `max_attempts`, returning the last error.
+async fn download_url_with_retry(url: &str, max_attempts: u32) -> Result<String, surf::Exception> {
+    let jitter_millis = Uniform::from(0..50);
+    let mut delay_millis = 100u64;
+
+    for attempt in 1..=max_attempts {
+        match download_url(url).await {
+            Ok(body) => return Ok(body),
+            Err(e) => {
+                if attempt == max_attempts {
+                    return Err(e);
+                }
+
+                let jitter = jitter_millis.sample(&mut rand::thread_rng());
+                let sleep_millis = delay_millis + jitter;
+                println!("    Attempt {} for {} failed ({:?}), retrying in {} ms", attempt, url, e, sleep_millis);
+                task::sleep(Duration::from_millis(sleep_millis)).await;
+                delay_millis *= 2;
+            },
+        }
+    }
+
+    unreachable!("loop always returns on the last attempt");
+}
+
+/// Demonstrates two things at once:
+///
+/// 1. Per-request deadlines: every download races a `task::sleep` via
+///    `download_url_with_timeout`, so one hanging server can no longer
+///    stall the whole batch.
+/// 2. Early cancellation: each download is wrapped with
+///    `future::abortable`, giving us an `AbortHandle`. Once the first
+///    `successes_needed` downloads have succeeded, we call `abort()`
+///    on every handle for a download that is still in flight.
+fn demo_downloading_urls_with_timeout_and_cancellation() {
+    let urls = vec![
+        "https://www.sharecast.com/equity/Anglo_American",
+        "https://www.sharecast.com/equity/Associated_British_Foods",
+        "https://www.sharecast.com/equity/Admiral_Group",
+        "https://www.sharecast.com/equity/Aberdeen_Asset_Management",
+        "https://www.sharecast.com/equity/Aggreko",
+        "https://www.sharecast.com/equity/Ashtead_Group",
+        "https://www.sharecast.com/equity/Antofagasta",
+        "https://www.sharecast.com/equity/Aviva",
+        "https://www.sharecast.com/equity/AstraZeneca",
+        "https://www.sharecast.com/equity/BAE_Systems",
+    ];
+
+    let timeout = Duration::from_secs(5);
+    let successes_needed = 5;
+
+    task::block_on(async {
+        let mut futures = FuturesUnordered::new();
+        let mut handles = Vec::new();
+
+        for url in &urls {
+            let (abortable_download, handle) = abortable(download_url_with_timeout(url, timeout));
+            handles.push(handle);
+            futures.push(abortable_download);
+        }
+
+        let mut successes = 0;
+        while let Some(return_val) = futures.next().await {
+            match return_val {
+                // The outer `Ok` means the abortable future ran to completion
+                // (it was not aborted); the nested `Result` is our own
+                // download-or-timeout outcome.
+                Ok(Ok(_body)) => {
+                    successes += 1;
+                    println!("    Download succeeded ({}/{})", successes, successes_needed);
+
+                    if successes == successes_needed {
+                        println!("    Reached {} successes, aborting remaining downloads", successes_needed);
+                        for handle in &handles {
+                            handle.abort();
+                        }
+                    }
+                },
+                Ok(Err(DownloadError::TimedOut)) => println!("    Download timed out after {:?}", timeout),
+                Ok(Err(DownloadError::Request(e))) => println!("    Got error {:?}", e),
+                Err(_aborted) => println!("    Download was aborted"),
+            }
+        }
+    });
+}
+
+fn demo_channel_coordination() {
+    task::block_on(async {
+        demo_oneshot_per_task().await;
+        demo_mpsc_fan_in().await;
+    });
+}
+
+/// Spawns one detached task per download with `task::spawn`, rather than
+/// holding every future in a single collection like `FuturesUnordered`.
+/// Each task gets its own `oneshot::Sender` and delivers its single
+/// result back down that channel when it's done; the matching
+/// `oneshot::Receiver`s are collected up front and awaited afterwards.
+async fn demo_oneshot_per_task() {
+    let urls = vec![
+        "https://www.sharecast.com/equity/Anglo_American",
+        "https://www.sharecast.com/equity/Associated_British_Foods",
+        "https://www.sharecast.com/equity/Admiral_Group",
+    ];
+
+    let mut receivers = Vec::new();
+    for url in urls {
+        let (sender, receiver) = oneshot::channel();
+        let url = url.to_string();
+
+        task::spawn(async move {
+            let result = download_url(&url).await;
+            // `send` only fails if the receiver was dropped; nothing to
+            // do about that here.
+            let _ = sender.send(result);
+        });
+
+        receivers.push(receiver);
+    }
+
+    for receiver in receivers {
+        match receiver.await {
+            Ok(Ok(body)) => println!("    oneshot: got body of length {}", body.len()),
+            Ok(Err(e)) => println!("    oneshot: got error {:?}", e),
+            Err(_canceled) => println!("    oneshot: sender was dropped without sending"),
+        }
+    }
+}
+
+/// Many producer tasks push downloaded bodies into a bounded
+/// `mpsc::channel`, and a single consumer task drains the receiver
+/// stream and accumulates the results. Because the channel is bounded,
+/// a producer's `send` will wait if the channel is full, applying
+/// backpressure instead of buffering everything in memory.
+async fn demo_mpsc_fan_in() {
+    let urls = vec![
+        "https://www.sharecast.com/equity/Aberdeen_Asset_Management",
+        "https://www.sharecast.com/equity/Aggreko",
+        "https://www.sharecast.com/equity/Ashtead_Group",
+    ];
+
+    let (sender, mut receiver) = mpsc::channel(2);
+
+    for url in urls {
+        let mut sender = sender.clone();
+        let url = url.to_string();
+
+        task::spawn(async move {
+            if let Ok(body) = download_url(&url).await {
+                let _ = sender.send(body).await;
+            }
+        });
+    }
+
+    // Drop our own sender so that once every cloned sender has also been
+    // dropped (i.e. every producer task has finished), the receiver
+    // stream ends instead of waiting forever.
+    drop(sender);
+
+    let mut bodies = Vec::new();
+    while let Some(body) = receiver.next().await {
+        bodies.push(body);
+    }
+
+    println!("    mpsc: consumer accumulated {} bodies", bodies.len());
+}
+
 fn demo_downloading_urls() {
     let urls = vec![
         "https://www.sharecast.com/equity/Anglo_American",
@@ -184,13 +407,123 @@ fn demo_downloading_urls() {
     // This time let's make our FuturesUnordered value by collecting
     // a set of futures.
     let mut futures = urls.iter()
-        .map(|url| download_url(url))
+        .map(|url| download_url_with_retry(url, 4))
         .collect::<FuturesUnordered<_>>();
 
     task::block_on(async {
         while let Some(return_val) = futures.next().await {
             match return_val {
                 Ok(body) => {
+                    // Hashing 200 passes over the body is genuinely CPU-heavy;
+                    // calling `compute_content_hash(&body)` directly here would
+                    // run on this task's executor thread and stall every other
+                    // future polled on it for as long as the hash takes. Instead
+                    // we offload it to async-std's blocking thread pool and
+                    // await the result, keeping the reactor free in the meantime.
+                    let hash = task::spawn_blocking(move || compute_content_hash(&body)).await;
+                    println!("    Computed content hash {:x} for body", hash);
+                },
+                Err(e) => println!("    Got error {:?}", e),
+            }
+        }
+    });
+}
+
+/// `FuturesUnordered` polls every future concurrently but yields results
+/// as soon as each one finishes, so the order of `urls` is lost. This
+/// demo pushes the same futures into a `FuturesOrdered` instead: they
+/// still all run concurrently, but `.next()` only yields a result once
+/// every earlier-pushed future has also resolved, so the bodies come
+/// back aligned with `urls` regardless of which request the server
+/// answered first.
+fn demo_downloading_urls_in_order() {
+    let urls = vec![
+        "https://www.sharecast.com/equity/Anglo_American",
+        "https://www.sharecast.com/equity/Associated_British_Foods",
+        "https://www.sharecast.com/equity/Admiral_Group",
+        "https://www.sharecast.com/equity/Aberdeen_Asset_Management",
+        "https://www.sharecast.com/equity/Aggreko",
+        "https://www.sharecast.com/equity/Ashtead_Group",
+        "https://www.sharecast.com/equity/Antofagasta",
+        "https://www.sharecast.com/equity/Aviva",
+        "https://www.sharecast.com/equity/AstraZeneca",
+        "https://www.sharecast.com/equity/BAE_Systems",
+        "https://www.sharecast.com/equity/Babcock_International_Group",
+        "https://www.sharecast.com/equity/British_American_Tobacco",
+        "https://www.sharecast.com/equity/Balfour_Beatty",
+        "https://www.sharecast.com/equity/Barratt_Developments",
+        "https://www.sharecast.com/equity/BG_Group",
+        "https://www.sharecast.com/equity/British_Land_Company",
+        "https://www.sharecast.com/equity/BHP_Group",
+        "https://www.sharecast.com/equity/Bunzl",
+        "https://www.sharecast.com/equity/BP",
+        "https://www.sharecast.com/equity/Burberry_Group",
+        "https://www.sharecast.com/equity/BT_Group",
+    ];
+
+    let mut futures = urls.iter()
+        .map(|url| download_url(url))
+        .collect::<FuturesOrdered<_>>();
+
+    task::block_on(async {
+        let mut index = 0;
+        while let Some(return_val) = futures.next().await {
+            match return_val {
+                Ok(body) => println!("    [{}] {} -> body of length {}, in input order", index, urls[index], body.len()),
+                Err(e) => println!("    [{}] {} -> error {:?}, in input order", index, urls[index], e),
+            }
+            index += 1;
+        }
+    });
+}
+
+/// Maps each URL to a `download_url` future and drives the resulting
+/// stream with `.buffer_unordered(concurrency)`, so at most
+/// `concurrency` downloads are ever in flight at once; a new one only
+/// starts as an earlier one completes. Reusable by anything that wants
+/// a concurrency-capped batch of downloads, not just this demo.
+fn download_urls_bounded<'a>(urls: &'a [&'a str], concurrency: usize) -> impl Stream<Item = Result<String, surf::Exception>> + 'a {
+    stream::iter(urls.iter().copied())
+        .map(download_url)
+        .buffer_unordered(concurrency)
+}
+
+/// Same URL list as `demo_downloading_urls`, but driven through
+/// `download_urls_bounded` instead of a `FuturesUnordered` that fires
+/// every request at once. Only `concurrency` downloads are ever in
+/// flight simultaneously. Results still arrive in completion order,
+/// not input order.
+fn demo_downloading_urls_bounded(concurrency: usize) {
+    let urls = vec![
+        "https://www.sharecast.com/equity/Anglo_American",
+        "https://www.sharecast.com/equity/Associated_British_Foods",
+        "https://www.sharecast.com/equity/Admiral_Group",
+        "https://www.sharecast.com/equity/Aberdeen_Asset_Management",
+        "https://www.sharecast.com/equity/Aggreko",
+        "https://www.sharecast.com/equity/Ashtead_Group",
+        "https://www.sharecast.com/equity/Antofagasta",
+        "https://www.sharecast.com/equity/Aviva",
+        "https://www.sharecast.com/equity/AstraZeneca",
+        "https://www.sharecast.com/equity/BAE_Systems",
+        "https://www.sharecast.com/equity/Babcock_International_Group",
+        "https://www.sharecast.com/equity/British_American_Tobacco",
+        "https://www.sharecast.com/equity/Balfour_Beatty",
+        "https://www.sharecast.com/equity/Barratt_Developments",
+        "https://www.sharecast.com/equity/BG_Group",
+        "https://www.sharecast.com/equity/British_Land_Company",
+        "https://www.sharecast.com/equity/BHP_Group",
+        "https://www.sharecast.com/equity/Bunzl",
+        "https://www.sharecast.com/equity/BP",
+        "https://www.sharecast.com/equity/Burberry_Group",
+        "https://www.sharecast.com/equity/BT_Group",
+    ];
+
+    task::block_on(async {
+        let mut results = download_urls_bounded(&urls, concurrency);
+
+        while let Some(return_val) = results.next().await {
+            match return_val {
+                Ok(_) => {
                     // Possibly do something useful with the body of the request here.
                 },
                 Err(e) => println!("    Got error {:?}", e),